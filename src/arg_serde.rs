@@ -0,0 +1,633 @@
+//! `serde::Serializer`/`serde::Deserializer` implementations that let [`Arg`] be converted to
+//! and from any `Serialize`/`DeserializeOwned` type, the same way `serde_json::Value` does.
+
+use std::collections::hash_map;
+use std::convert::TryFrom;
+use std::fmt;
+use std::num::NonZeroU64;
+use std::slice;
+
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+use serde::ser::{
+    self, Serialize, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant,
+    SerializeTuple, SerializeTupleStruct, SerializeTupleVariant,
+};
+
+use crate::common::{Arg, WampDict, WampInteger, WampList};
+use crate::error::*;
+
+impl Arg {
+    /// Serializes any `T: Serialize` into an [`Arg`], mirroring `serde_json::to_value`.
+    pub fn serialize_from<T: Serialize + ?Sized>(value: &T) -> Result<Arg, WampError> {
+        value.serialize(ArgSerializer)
+    }
+
+    /// Deserializes this [`Arg`] into any `T: DeserializeOwned`, mirroring
+    /// `serde_json::from_value`.
+    pub fn deserialize_into<T: DeserializeOwned>(&self) -> Result<T, WampError> {
+        T::deserialize(ArgDeserializer(self))
+    }
+}
+
+impl ser::Error for WampError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        WampError::InvalidArgument(msg.to_string())
+    }
+}
+
+impl de::Error for WampError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        WampError::InvalidArgument(msg.to_string())
+    }
+}
+
+struct ArgSerializer;
+
+impl ser::Serializer for ArgSerializer {
+    type Ok = Arg;
+    type Error = WampError;
+
+    type SerializeSeq = ArgSeqSerializer;
+    type SerializeTuple = ArgSeqSerializer;
+    type SerializeTupleStruct = ArgSeqSerializer;
+    type SerializeTupleVariant = ArgVariantSerializer;
+    type SerializeMap = ArgMapSerializer;
+    type SerializeStruct = ArgMapSerializer;
+    type SerializeStructVariant = ArgVariantSerializer;
+
+    fn serialize_bool(self, v: bool) -> Result<Arg, WampError> {
+        Ok(Arg::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Arg, WampError> {
+        self.serialize_i64(v as i64)
+    }
+    fn serialize_i16(self, v: i16) -> Result<Arg, WampError> {
+        self.serialize_i64(v as i64)
+    }
+    fn serialize_i32(self, v: i32) -> Result<Arg, WampError> {
+        self.serialize_i64(v as i64)
+    }
+    fn serialize_i64(self, v: i64) -> Result<Arg, WampError> {
+        let as_usize = WampInteger::try_from(v).map_err(|_| {
+            WampError::InvalidArgument(format!(
+                "signed integer {} does not fit in a WampInteger (usize)",
+                v
+            ))
+        })?;
+        Ok(Arg::Integer(as_usize))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Arg, WampError> {
+        self.serialize_u64(v as u64)
+    }
+    fn serialize_u16(self, v: u16) -> Result<Arg, WampError> {
+        self.serialize_u64(v as u64)
+    }
+    fn serialize_u32(self, v: u32) -> Result<Arg, WampError> {
+        self.serialize_u64(v as u64)
+    }
+    fn serialize_u64(self, v: u64) -> Result<Arg, WampError> {
+        let as_usize = WampInteger::try_from(v).map_err(|_| {
+            WampError::InvalidArgument(format!(
+                "integer {} does not fit in a WampInteger (usize)",
+                v
+            ))
+        })?;
+        Ok(Arg::Integer(as_usize))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Arg, WampError> {
+        self.serialize_f64(v as f64)
+    }
+    fn serialize_f64(self, _v: f64) -> Result<Arg, WampError> {
+        Err(WampError::InvalidArgument(
+            "floating point values have no WAMP Arg representation".to_string(),
+        ))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Arg, WampError> {
+        Ok(Arg::String(v.to_string()))
+    }
+    fn serialize_str(self, v: &str) -> Result<Arg, WampError> {
+        Ok(Arg::String(v.to_string()))
+    }
+    fn serialize_bytes(self, v: &[u8]) -> Result<Arg, WampError> {
+        Ok(Arg::List(
+            v.iter().map(|b| Arg::Integer(*b as WampInteger)).collect(),
+        ))
+    }
+
+    fn serialize_none(self) -> Result<Arg, WampError> {
+        Ok(Arg::None)
+    }
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Arg, WampError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Arg, WampError> {
+        Ok(Arg::None)
+    }
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Arg, WampError> {
+        Ok(Arg::None)
+    }
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Arg, WampError> {
+        Ok(Arg::String(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Arg, WampError> {
+        value.serialize(self)
+    }
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Arg, WampError> {
+        let mut dict = WampDict::new();
+        dict.insert(variant.to_string(), Arg::serialize_from(value)?);
+        Ok(Arg::Dict(dict))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<ArgSeqSerializer, WampError> {
+        Ok(ArgSeqSerializer {
+            items: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+    fn serialize_tuple(self, len: usize) -> Result<ArgSeqSerializer, WampError> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<ArgSeqSerializer, WampError> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<ArgVariantSerializer, WampError> {
+        Ok(ArgVariantSerializer {
+            variant,
+            items: Vec::with_capacity(len),
+            fields: WampDict::new(),
+        })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<ArgMapSerializer, WampError> {
+        Ok(ArgMapSerializer {
+            dict: WampDict::new(),
+            pending_key: None,
+        })
+    }
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<ArgMapSerializer, WampError> {
+        Ok(ArgMapSerializer {
+            dict: WampDict::new(),
+            pending_key: None,
+        })
+    }
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<ArgVariantSerializer, WampError> {
+        Ok(ArgVariantSerializer {
+            variant,
+            items: Vec::new(),
+            fields: WampDict::new(),
+        })
+    }
+}
+
+struct ArgSeqSerializer {
+    items: WampList,
+}
+
+impl SerializeSeq for ArgSeqSerializer {
+    type Ok = Arg;
+    type Error = WampError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), WampError> {
+        self.items.push(Arg::serialize_from(value)?);
+        Ok(())
+    }
+    fn end(self) -> Result<Arg, WampError> {
+        Ok(Arg::List(self.items))
+    }
+}
+impl SerializeTuple for ArgSeqSerializer {
+    type Ok = Arg;
+    type Error = WampError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), WampError> {
+        SerializeSeq::serialize_element(self, value)
+    }
+    fn end(self) -> Result<Arg, WampError> {
+        SerializeSeq::end(self)
+    }
+}
+impl SerializeTupleStruct for ArgSeqSerializer {
+    type Ok = Arg;
+    type Error = WampError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), WampError> {
+        SerializeSeq::serialize_element(self, value)
+    }
+    fn end(self) -> Result<Arg, WampError> {
+        SerializeSeq::end(self)
+    }
+}
+
+/// Key used when serializing a string-like [`Arg`] as a `WampDict` key; any other variant is
+/// rejected since `WampDict` keys MUST be strings.
+fn require_string_key(key: Arg) -> Result<String, WampError> {
+    match key {
+        Arg::String(s) | Arg::Uri(s) => Ok(s),
+        other => Err(WampError::InvalidArgument(format!(
+            "WampDict keys must be strings, got {:?}",
+            other
+        ))),
+    }
+}
+
+struct ArgMapSerializer {
+    dict: WampDict,
+    pending_key: Option<String>,
+}
+
+impl SerializeMap for ArgMapSerializer {
+    type Ok = Arg;
+    type Error = WampError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), WampError> {
+        self.pending_key = Some(require_string_key(Arg::serialize_from(key)?)?);
+        Ok(())
+    }
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), WampError> {
+        let key = self
+            .pending_key
+            .take()
+            .expect("serialize_value called before serialize_key");
+        self.dict.insert(key, Arg::serialize_from(value)?);
+        Ok(())
+    }
+    fn end(self) -> Result<Arg, WampError> {
+        Ok(Arg::Dict(self.dict))
+    }
+}
+impl SerializeStruct for ArgMapSerializer {
+    type Ok = Arg;
+    type Error = WampError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), WampError> {
+        self.dict.insert(key.to_string(), Arg::serialize_from(value)?);
+        Ok(())
+    }
+    fn end(self) -> Result<Arg, WampError> {
+        Ok(Arg::Dict(self.dict))
+    }
+}
+
+struct ArgVariantSerializer {
+    variant: &'static str,
+    items: WampList,
+    fields: WampDict,
+}
+
+impl SerializeTupleVariant for ArgVariantSerializer {
+    type Ok = Arg;
+    type Error = WampError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), WampError> {
+        self.items.push(Arg::serialize_from(value)?);
+        Ok(())
+    }
+    fn end(self) -> Result<Arg, WampError> {
+        let mut dict = WampDict::new();
+        dict.insert(self.variant.to_string(), Arg::List(self.items));
+        Ok(Arg::Dict(dict))
+    }
+}
+impl SerializeStructVariant for ArgVariantSerializer {
+    type Ok = Arg;
+    type Error = WampError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), WampError> {
+        self.fields
+            .insert(key.to_string(), Arg::serialize_from(value)?);
+        Ok(())
+    }
+    fn end(self) -> Result<Arg, WampError> {
+        let mut dict = WampDict::new();
+        dict.insert(self.variant.to_string(), Arg::Dict(self.fields));
+        Ok(Arg::Dict(dict))
+    }
+}
+
+struct ArgDeserializer<'a>(&'a Arg);
+
+impl<'de, 'a> de::Deserializer<'de> for ArgDeserializer<'a> {
+    type Error = WampError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, WampError> {
+        match self.0 {
+            Arg::Uri(s) | Arg::String(s) => visitor.visit_str(s),
+            Arg::Id(id) => visitor.visit_u64(NonZeroU64::from(*id).get()),
+            Arg::Integer(i) => visitor.visit_u64(*i as u64),
+            Arg::Bool(b) => visitor.visit_bool(*b),
+            Arg::Dict(dict) => visitor.visit_map(ArgMapAccess {
+                iter: dict.iter(),
+                value: None,
+            }),
+            Arg::List(list) => visitor.visit_seq(ArgSeqAccess { iter: list.iter() }),
+            Arg::None => visitor.visit_none(),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, WampError> {
+        match self.0 {
+            Arg::None => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, WampError> {
+        match self.0 {
+            Arg::None => visitor.visit_unit(),
+            other => Err(WampError::InvalidArgument(format!(
+                "expected unit, got {:?}",
+                other
+            ))),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, WampError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, WampError> {
+        match self.0 {
+            Arg::String(variant) => visitor.visit_enum(ArgEnumAccess {
+                variant: variant.clone(),
+                payload: None,
+            }),
+            Arg::Dict(dict) if dict.len() == 1 => {
+                let (variant, payload) = dict.iter().next().unwrap();
+                visitor.visit_enum(ArgEnumAccess {
+                    variant: variant.clone(),
+                    payload: Some(payload),
+                })
+            }
+            other => Err(WampError::InvalidArgument(format!(
+                "expected a string (unit variant) or single-entry dict (variant payload), got {:?}",
+                other
+            ))),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct ArgEnumAccess<'a> {
+    variant: String,
+    payload: Option<&'a Arg>,
+}
+
+impl<'de, 'a> de::EnumAccess<'de> for ArgEnumAccess<'a> {
+    type Error = WampError;
+    type Variant = ArgVariantAccess<'a>;
+
+    fn variant_seed<T: de::DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<(T::Value, Self::Variant), WampError> {
+        let value = seed.deserialize(self.variant.into_deserializer())?;
+        Ok((value, ArgVariantAccess { payload: self.payload }))
+    }
+}
+
+struct ArgVariantAccess<'a> {
+    payload: Option<&'a Arg>,
+}
+
+impl<'de, 'a> de::VariantAccess<'de> for ArgVariantAccess<'a> {
+    type Error = WampError;
+
+    fn unit_variant(self) -> Result<(), WampError> {
+        match self.payload {
+            None => Ok(()),
+            Some(other) => Err(WampError::InvalidArgument(format!(
+                "expected a unit variant, got payload {:?}",
+                other
+            ))),
+        }
+    }
+
+    fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, WampError> {
+        let payload = self
+            .payload
+            .ok_or_else(|| WampError::InvalidArgument("expected a newtype variant payload".to_string()))?;
+        seed.deserialize(ArgDeserializer(payload))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, WampError> {
+        match self.payload {
+            Some(Arg::List(list)) => visitor.visit_seq(ArgSeqAccess { iter: list.iter() }),
+            other => Err(WampError::InvalidArgument(format!(
+                "expected a list payload for a tuple variant, got {:?}",
+                other
+            ))),
+        }
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, WampError> {
+        match self.payload {
+            Some(Arg::Dict(dict)) => visitor.visit_map(ArgMapAccess {
+                iter: dict.iter(),
+                value: None,
+            }),
+            other => Err(WampError::InvalidArgument(format!(
+                "expected a dict payload for a struct variant, got {:?}",
+                other
+            ))),
+        }
+    }
+}
+
+struct ArgSeqAccess<'a> {
+    iter: slice::Iter<'a, Arg>,
+}
+
+impl<'de, 'a> de::SeqAccess<'de> for ArgSeqAccess<'a> {
+    type Error = WampError;
+
+    fn next_element_seed<T: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, WampError> {
+        match self.iter.next() {
+            Some(value) => seed.deserialize(ArgDeserializer(value)).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+struct ArgMapAccess<'a> {
+    iter: hash_map::Iter<'a, String, Arg>,
+    value: Option<&'a Arg>,
+}
+
+impl<'de, 'a> de::MapAccess<'de> for ArgMapAccess<'a> {
+    type Error = WampError;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, WampError> {
+        match self.iter.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(key.clone().into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, WampError> {
+        let value = self
+            .value
+            .take()
+            .expect("next_value_seed called before next_key_seed");
+        seed.deserialize(ArgDeserializer(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Point {
+        x: u32,
+        y: u32,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    enum Shape {
+        Circle(u32),
+        Rect { w: u32, h: u32 },
+        Empty,
+    }
+
+    #[test]
+    fn round_trips_struct() {
+        let point = Point { x: 1, y: 2 };
+        let arg = Arg::serialize_from(&point).unwrap();
+        assert_eq!(arg.deserialize_into::<Point>().unwrap(), point);
+    }
+
+    #[test]
+    fn round_trips_tuple_and_struct_variants() {
+        for shape in [Shape::Circle(2), Shape::Rect { w: 3, h: 4 }] {
+            let arg = Arg::serialize_from(&shape).unwrap();
+            assert_eq!(arg.deserialize_into::<Shape>().unwrap(), shape);
+        }
+    }
+
+    #[test]
+    fn round_trips_unit_variant() {
+        let arg = Arg::serialize_from(&Shape::Empty).unwrap();
+        assert_eq!(arg.deserialize_into::<Shape>().unwrap(), Shape::Empty);
+    }
+
+    #[test]
+    fn round_trips_unit() {
+        let arg = Arg::serialize_from(&()).unwrap();
+        assert_eq!(arg, Arg::None);
+        arg.deserialize_into::<()>().unwrap();
+    }
+
+    #[test]
+    fn round_trips_option() {
+        let some: Option<i32> = Some(5);
+        let none: Option<i32> = None;
+
+        assert_eq!(
+            Arg::serialize_from(&some)
+                .unwrap()
+                .deserialize_into::<Option<i32>>()
+                .unwrap(),
+            some
+        );
+        assert_eq!(
+            Arg::serialize_from(&none)
+                .unwrap()
+                .deserialize_into::<Option<i32>>()
+                .unwrap(),
+            none
+        );
+    }
+
+    #[test]
+    fn rejects_non_string_map_keys() {
+        use std::collections::HashMap;
+
+        let map = HashMap::from([(1i32, 2i32)]);
+        assert!(Arg::serialize_from(&map).is_err());
+    }
+
+    #[test]
+    fn rejects_signed_integers() {
+        assert!(Arg::serialize_from(&-1i64).is_err());
+    }
+}
@@ -5,11 +5,15 @@ use std::hash::Hash;
 use std::num::NonZeroU64;
 use std::pin::Pin;
 
+use crate::context::CallContext;
 use crate::error::*;
 
 use log::*;
 use serde::{Deserialize, Serialize};
 
+// Used by the client's HELLO handshake once that's wired up; not read by anything in this
+// checkout yet.
+#[allow(dead_code)]
 pub(crate) const DEFAULT_AGENT_STR: &str =
     concat!(env!("CARGO_PKG_NAME"), "_rs-", env!("CARGO_PKG_VERSION"));
 
@@ -32,6 +36,12 @@ impl From<WampId> for NonZeroU64 {
     }
 }
 
+impl From<NonZeroU64> for WampId {
+    fn from(id: NonZeroU64) -> Self {
+        Self(id)
+    }
+}
+
 impl WampId {
     /// IDs in the global scope MUST be drawn randomly from a uniform distribution over the complete
     /// range [1, 2^53]
@@ -59,8 +69,8 @@ pub type WampArgs = Option<WampList>;
 pub type WampKwArgs = Option<WampDict>;
 
 /// Generic enum that can hold any concrete WAMP value
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(untagged)]
-#[derive(Serialize, Deserialize, Debug)]
 pub enum Arg {
     /// uri: a string URI as defined in URIs
     Uri(WampUri),
@@ -168,5 +178,6 @@ pub type GenericFuture = Pin<Box<dyn Future<Output = Result<(), WampError>> + Se
 /// Type returned by RPC functions
 pub type RpcFuture =
     Pin<Box<dyn Future<Output = Result<(WampArgs, WampKwArgs), WampError>> + Send>>;
-/// Generic function that can receive RPC calls
-pub type RpcFunc = Box<dyn Fn(WampArgs, WampKwArgs) -> RpcFuture + Send + Sync>;
+/// Generic function that can receive RPC calls, optionally receiving the [`CallContext`]
+/// extracted from the invocation's `Details` dict when the caller propagated one
+pub type RpcFunc = Box<dyn Fn(WampArgs, WampKwArgs, Option<CallContext>) -> RpcFuture + Send + Sync>;
@@ -0,0 +1,118 @@
+use log::debug;
+
+use crate::common::{Arg, WampDict};
+
+const TRACE_ID_KEY: &str = "x_trace_id";
+const PARENT_SPAN_ID_KEY: &str = "x_parent_span_id";
+const BAGGAGE_KEY: &str = "x_baggage";
+
+/// Distributed tracing context propagated across a WAMP `CALL`/`PUBLISH`.
+///
+/// The caller attaches a `CallContext` to a request; [`CallContext::inject`] writes it into the
+/// `Details`/`Options` dict of the outgoing message, and [`CallContext::extract`] reads it back
+/// out on the callee/subscriber side so an [`RpcFunc`](crate::RpcFunc) handler can open a span
+/// linked to the caller's.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallContext {
+    /// Identifies the distributed trace this call is part of.
+    pub trace_id: String,
+    /// The id of the span that issued this call, used as the parent of any span the callee opens
+    /// while handling it.
+    pub parent_span_id: String,
+    /// Additional key/value baggage propagated alongside the trace/span ids.
+    pub baggage: WampDict,
+}
+
+impl CallContext {
+    /// Creates a new context with empty baggage.
+    pub fn new(trace_id: impl Into<String>, parent_span_id: impl Into<String>) -> Self {
+        Self {
+            trace_id: trace_id.into(),
+            parent_span_id: parent_span_id.into(),
+            baggage: WampDict::new(),
+        }
+    }
+
+    /// Injects this context into the `Details`/`Options` dict of an outgoing `CALL` or
+    /// `PUBLISH` message.
+    pub fn inject(&self, details: &mut WampDict) {
+        details.insert(TRACE_ID_KEY.to_string(), Arg::String(self.trace_id.clone()));
+        details.insert(
+            PARENT_SPAN_ID_KEY.to_string(),
+            Arg::String(self.parent_span_id.clone()),
+        );
+        if !self.baggage.is_empty() {
+            details.insert(BAGGAGE_KEY.to_string(), Arg::Dict(self.baggage.clone()));
+        }
+    }
+
+    /// Extracts a previously injected context from an incoming `Details`/`Options` dict.
+    ///
+    /// Returns `None` if the dict carries no trace/parent span ids, which is the common case
+    /// when talking to a peer that doesn't propagate tracing context.
+    pub fn extract(details: &WampDict) -> Option<Self> {
+        let trace_id = match details.get(TRACE_ID_KEY)? {
+            Arg::String(s) => s.clone(),
+            _ => return None,
+        };
+        let parent_span_id = match details.get(PARENT_SPAN_ID_KEY)? {
+            Arg::String(s) => s.clone(),
+            _ => return None,
+        };
+        let baggage = match details.get(BAGGAGE_KEY) {
+            Some(Arg::Dict(dict)) => dict.clone(),
+            _ => WampDict::new(),
+        };
+
+        Some(Self {
+            trace_id,
+            parent_span_id,
+            baggage,
+        })
+    }
+
+    /// Logs that `uri` is being handled as part of this trace, for deployments that rely on
+    /// `log` rather than enabling the `tracing` feature.
+    pub fn log_received(&self, uri: &str) {
+        debug!(
+            "handling '{}' as part of trace {} (parent span {})",
+            uri, self.trace_id, self.parent_span_id
+        );
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl CallContext {
+    /// Opens a `tracing` span for `name`, linked to the caller's span via this context's trace
+    /// and parent span ids.
+    pub fn span(&self, name: &'static str) -> tracing::Span {
+        tracing::info_span!(
+            "wamp_rpc",
+            otel.name = name,
+            trace_id = %self.trace_id,
+            parent_span_id = %self.parent_span_id,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inject_then_extract_round_trips() {
+        let mut ctx = CallContext::new("trace-1", "span-1");
+        ctx.baggage
+            .insert("user_id".to_string(), Arg::String("42".to_string()));
+
+        let mut details = WampDict::new();
+        ctx.inject(&mut details);
+
+        assert_eq!(CallContext::extract(&details), Some(ctx));
+    }
+
+    #[test]
+    fn extract_returns_none_without_injected_context() {
+        assert_eq!(CallContext::extract(&WampDict::new()), None);
+    }
+}
@@ -0,0 +1,18 @@
+use std::fmt;
+
+/// Errors that can occur while building, sending, or handling WAMP messages.
+#[derive(Debug)]
+pub enum WampError {
+    /// A value couldn't be converted to/from its expected WAMP representation.
+    InvalidArgument(String),
+}
+
+impl fmt::Display for WampError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WampError::InvalidArgument(msg) => write!(f, "invalid argument: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for WampError {}
@@ -0,0 +1,14 @@
+mod arg_serde;
+mod common;
+mod context;
+mod error;
+mod id;
+mod typed;
+mod uri;
+
+pub use common::*;
+pub use context::*;
+pub use error::*;
+pub use id::*;
+pub use typed::*;
+pub use uri::*;
@@ -0,0 +1,235 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::common::{Arg, RpcFunc, WampArgs, WampId, WampKwArgs};
+use crate::context::CallContext;
+use crate::error::*;
+
+/// Boxed future returned by [`TypedCall`]'s raw and typed call/register methods.
+type CallFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, WampError>> + Send + 'a>>;
+
+/// Converts a serializable value into the `(WampArgs, WampKwArgs)` pair expected by a `CALL`/
+/// `YIELD` message: sequences become positional arguments, maps/structs become keyword
+/// arguments, unit/`None` becomes no arguments at all, and anything else becomes a single
+/// positional argument.
+fn to_wamp_args<T: Serialize>(value: &T) -> Result<(WampArgs, WampKwArgs), WampError> {
+    Ok(match Arg::serialize_from(value)? {
+        Arg::List(list) => (Some(list), None),
+        Arg::Dict(dict) => (None, Some(dict)),
+        Arg::None => (None, None),
+        other => (Some(vec![other]), None),
+    })
+}
+
+/// Reassembles the `(WampArgs, WampKwArgs)` pair received in a `RESULT`/`INVOCATION` message
+/// into a single [`Arg`]. Non-empty keyword arguments become a [`Arg::Dict`] and any positional
+/// arguments are then ignored; otherwise a one-element argument list collapses to its sole
+/// element, no arguments give [`Arg::None`], and two or more positional arguments are an error.
+fn from_wamp_args(args: WampArgs, kwargs: WampKwArgs) -> Result<Arg, WampError> {
+    if let Some(dict) = kwargs.filter(|d| !d.is_empty()) {
+        return Ok(Arg::Dict(dict));
+    }
+
+    match args {
+        None => Ok(Arg::None),
+        Some(list) => match list.len() {
+            0 => Ok(Arg::None),
+            1 => Ok(list.into_iter().next().unwrap()),
+            n => Err(WampError::InvalidArgument(format!(
+                "expected a single return value, got {} positional arguments",
+                n
+            ))),
+        },
+    }
+}
+
+/// Invokes or registers remote procedures using native Rust types instead of hand-building
+/// `WampArgs`/`WampKwArgs`.
+///
+/// Implementors only need to provide [`TypedCall::raw_call`] and [`TypedCall::raw_register`];
+/// `call_typed`/`register_typed` are provided in terms of those.
+pub trait TypedCall: Sync {
+    /// Performs an untyped RPC call, returning the raw `(WampArgs, WampKwArgs)` reply. `ctx`, if
+    /// given, is injected into the outgoing `CALL`'s `Details` so the callee can extract it.
+    fn raw_call(
+        &self,
+        uri: &str,
+        args: WampArgs,
+        kwargs: WampKwArgs,
+        ctx: Option<CallContext>,
+    ) -> CallFuture<'_, (WampArgs, WampKwArgs)>;
+
+    /// Registers a raw [`RpcFunc`] under `uri`.
+    fn raw_register(&self, uri: &str, func: RpcFunc) -> CallFuture<'_, WampId>;
+
+    /// Calls `uri`, serializing `args` into positional or keyword WAMP arguments and
+    /// deserializing the single return value into `R`. `ctx`, if given, is propagated to the
+    /// callee the same way as in [`TypedCall::raw_call`].
+    fn call_typed<'a, A, R>(
+        &'a self,
+        uri: &'a str,
+        args: &'a A,
+        ctx: Option<CallContext>,
+    ) -> CallFuture<'a, R>
+    where
+        A: Serialize + Sync,
+        R: DeserializeOwned + 'a,
+    {
+        Box::pin(async move {
+            let (args, kwargs) = to_wamp_args(args)?;
+            let (ret_args, ret_kwargs) = self.raw_call(uri, args, kwargs, ctx).await?;
+            from_wamp_args(ret_args, ret_kwargs)?.deserialize_into()
+        })
+    }
+
+    /// Registers `uri` with a handler that receives its argument already deserialized into `A`
+    /// and returns `R`, which is re-serialized into the WAMP reply. Two or more positional
+    /// arguments are deserialized as a sequence/tuple `A`; a single positional argument is
+    /// deserialized as `A` itself.
+    fn register_typed<A, R, F, Fut>(&self, uri: &str, func: F) -> CallFuture<'_, WampId>
+    where
+        A: DeserializeOwned + Send + 'static,
+        R: Serialize + Send + 'static,
+        F: Fn(A) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<R, WampError>> + Send + 'static,
+    {
+        let func = std::sync::Arc::new(func);
+        // The tracing context isn't part of the typed call/response payload, so handlers
+        // registered here don't see it; use `raw_register` directly if that's needed.
+        let wrapped: RpcFunc = Box::new(move |args: WampArgs, kwargs: WampKwArgs, _ctx| {
+            let func = func.clone();
+            Box::pin(async move {
+                let input = match args {
+                    Some(list) if list.len() > 1 && kwargs.as_ref().map_or(true, |d| d.is_empty()) => {
+                        Arg::List(list)
+                    }
+                    args => from_wamp_args(args, kwargs)?,
+                };
+                let input: A = input.deserialize_into()?;
+                let output = func(input).await?;
+                to_wamp_args(&output)
+            })
+        });
+
+        self.raw_register(uri, wrapped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU64;
+    use std::sync::Mutex;
+    use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+    use super::*;
+
+    // None of these tests' futures ever return `Poll::Pending`, so a waker that does nothing is
+    // enough to drive them to completion.
+    fn block_on<F: Future>(mut fut: F) -> F::Output {
+        fn noop(_: *const ()) {}
+        fn clone(_: *const ()) -> RawWaker {
+            RawWaker::new(std::ptr::null(), &VTABLE)
+        }
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+
+        let waker = unsafe { Waker::from_raw(RawWaker::new(std::ptr::null(), &VTABLE)) };
+        let mut cx = Context::from_waker(&waker);
+        // Safety: `fut` is never moved again after being pinned here.
+        let mut fut = unsafe { Pin::new_unchecked(&mut fut) };
+        loop {
+            if let Poll::Ready(val) = fut.as_mut().poll(&mut cx) {
+                return val;
+            }
+        }
+    }
+
+    struct MockCaller {
+        reply: Mutex<Option<(WampArgs, WampKwArgs)>>,
+    }
+
+    impl TypedCall for MockCaller {
+        fn raw_call(
+            &self,
+            _uri: &str,
+            _args: WampArgs,
+            _kwargs: WampKwArgs,
+            _ctx: Option<CallContext>,
+        ) -> CallFuture<'_, (WampArgs, WampKwArgs)> {
+            let reply = self.reply.lock().unwrap().take().expect("reply already consumed");
+            Box::pin(async move { Ok(reply) })
+        }
+
+        fn raw_register(&self, _uri: &str, _func: RpcFunc) -> CallFuture<'_, WampId> {
+            unimplemented!("not exercised by these tests")
+        }
+    }
+
+    #[test]
+    fn call_typed_with_no_return_value_succeeds() {
+        let caller = MockCaller {
+            reply: Mutex::new(Some((None, None))),
+        };
+
+        let result: Result<(), WampError> = block_on(caller.call_typed("some.uri", &(), None));
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn call_typed_deserializes_single_return_value() {
+        let caller = MockCaller {
+            reply: Mutex::new(Some((Some(vec![Arg::Integer(42)]), None))),
+        };
+
+        let result: u32 = block_on(caller.call_typed("some.uri", &(), None)).unwrap();
+        assert_eq!(result, 42);
+    }
+
+    #[test]
+    fn call_typed_rejects_wrong_arity_reply() {
+        let caller = MockCaller {
+            reply: Mutex::new(Some((Some(vec![Arg::Integer(1), Arg::Integer(2)]), None))),
+        };
+
+        let result: Result<u32, WampError> = block_on(caller.call_typed("some.uri", &(), None));
+        assert!(result.is_err());
+    }
+
+    struct MockRegistrar {
+        registered: Mutex<Option<RpcFunc>>,
+    }
+
+    impl TypedCall for MockRegistrar {
+        fn raw_call(
+            &self,
+            _uri: &str,
+            _args: WampArgs,
+            _kwargs: WampKwArgs,
+            _ctx: Option<CallContext>,
+        ) -> CallFuture<'_, (WampArgs, WampKwArgs)> {
+            unimplemented!("not exercised by this test")
+        }
+
+        fn raw_register(&self, _uri: &str, func: RpcFunc) -> CallFuture<'_, WampId> {
+            *self.registered.lock().unwrap() = Some(func);
+            Box::pin(async move { Ok(WampId::from(NonZeroU64::new(1).unwrap())) })
+        }
+    }
+
+    #[test]
+    fn register_typed_wraps_a_func_that_deserializes_args_and_reserializes_the_reply() {
+        let registrar = MockRegistrar {
+            registered: Mutex::new(None),
+        };
+
+        block_on(registrar.register_typed("some.uri", |input: u32| async move { Ok(input + 1) }))
+            .unwrap();
+
+        let wrapped = registrar.registered.lock().unwrap().take().unwrap();
+        let (args, kwargs) = block_on(wrapped(Some(vec![Arg::Integer(41)]), None, None)).unwrap();
+        assert_eq!(args, Some(vec![Arg::Integer(42)]));
+        assert_eq!(kwargs, None);
+    }
+}
@@ -0,0 +1,144 @@
+//! Loose URI validation and wildcard URI patterns, alongside [`crate::is_valid_strict_uri`]'s
+//! strict rules.
+
+use log::warn;
+
+use crate::common::{is_valid_strict_uri, WampUri};
+
+/// Which URI grammar to validate a concrete (non-pattern) URI against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UriValidationMode {
+    /// Each `.`-separated token must be lowercase alphanumeric characters or underscores.
+    Strict,
+    /// Each `.`-separated token may be any non-empty run of characters other than whitespace,
+    /// `.` or `#`.
+    Loose,
+}
+
+/// Returns whether `uri` is valid under `mode`.
+pub fn is_valid_uri<T: AsRef<str>>(uri: T, mode: UriValidationMode) -> bool {
+    match mode {
+        UriValidationMode::Strict => is_valid_strict_uri(uri),
+        UriValidationMode::Loose => is_valid_loose_uri(uri),
+    }
+}
+
+fn is_valid_loose_uri<T: AsRef<str>>(in_uri: T) -> bool {
+    let uri: &str = in_uri.as_ref();
+    if uri.is_empty() {
+        warn!("URI cannot be empty");
+        return false;
+    }
+
+    if uri.starts_with("wamp.") {
+        warn!("URI '{}' cannot start with 'wamp'", uri);
+        return false;
+    }
+
+    for token in uri.split('.') {
+        if token.is_empty() {
+            warn!("URI '{}' contains a zero length token", uri);
+            return false;
+        }
+        if token.chars().any(|c| c.is_whitespace() || c == '#') {
+            warn!(
+                "URI '{}' contains a whitespace or '#' character",
+                uri
+            );
+            return false;
+        }
+    }
+
+    true
+}
+
+/// A wildcard URI pattern: an empty token between two dots (e.g. `com.myapp..create`) matches
+/// any single component in that position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UriPattern {
+    components: Vec<Option<String>>,
+}
+
+impl UriPattern {
+    /// Parses `pattern`, treating an empty `.`-separated component as a wildcard.
+    pub fn parse<T: AsRef<str>>(pattern: T) -> Self {
+        let components = pattern
+            .as_ref()
+            .split('.')
+            .map(|token| {
+                if token.is_empty() {
+                    None
+                } else {
+                    Some(token.to_string())
+                }
+            })
+            .collect();
+
+        Self { components }
+    }
+
+    /// Returns whether `concrete` matches this pattern: the same number of `.`-separated
+    /// components, with every non-wildcard component matching exactly.
+    pub fn matches(&self, concrete: &WampUri) -> bool {
+        let concrete_components: Vec<&str> = concrete.split('.').collect();
+        if concrete_components.len() != self.components.len() {
+            return false;
+        }
+
+        self.components
+            .iter()
+            .zip(concrete_components.iter())
+            .all(|(pattern, concrete)| match pattern {
+                None => true,
+                Some(exact) => exact == concrete,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loose_rejects_empty_uri() {
+        assert!(!is_valid_uri("", UriValidationMode::Loose));
+    }
+
+    #[test]
+    fn loose_rejects_wamp_prefix() {
+        assert!(!is_valid_uri("wamp.session.on_join", UriValidationMode::Loose));
+    }
+
+    #[test]
+    fn loose_rejects_whitespace_and_hash() {
+        assert!(!is_valid_uri("com.myapp. create", UriValidationMode::Loose));
+        assert!(!is_valid_uri("com.myapp.create#1", UriValidationMode::Loose));
+    }
+
+    #[test]
+    fn loose_accepts_characters_strict_would_reject() {
+        assert!(is_valid_uri("com.MyApp.Create-Widget", UriValidationMode::Loose));
+    }
+
+    #[test]
+    fn loose_rejects_zero_length_token() {
+        assert!(!is_valid_uri("com..create", UriValidationMode::Loose));
+    }
+
+    #[test]
+    fn pattern_matches_wildcard_at_start_middle_and_end() {
+        assert!(UriPattern::parse(".myapp.create").matches(&"com.myapp.create".to_string()));
+        assert!(UriPattern::parse("com..create").matches(&"com.myapp.create".to_string()));
+        assert!(UriPattern::parse("com.myapp.").matches(&"com.myapp.create".to_string()));
+    }
+
+    #[test]
+    fn pattern_rejects_mismatched_exact_component() {
+        assert!(!UriPattern::parse("com.myapp.create").matches(&"com.myapp.delete".to_string()));
+    }
+
+    #[test]
+    fn pattern_rejects_mismatched_arity() {
+        assert!(!UriPattern::parse("com.myapp.create").matches(&"com.myapp.sub.create".to_string()));
+    }
+}